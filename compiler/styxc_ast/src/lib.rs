@@ -1,9 +1,11 @@
 use std::error::Error;
 use std::str::FromStr;
 
-use crate::passes::{validate_symbols, validate_types};
+use crate::passes::{validate_no_chained_comparisons, validate_symbols, validate_types};
 
+pub mod lowering;
 mod passes;
+pub mod parser;
 
 /// A struct represnting a span of a string. The first paramteter is the start index of the span,
 /// and the second parameter is the end index of the span (inclusive).
@@ -154,6 +156,7 @@ impl FromStr for UnOpKind {
             "~" => Ok(Not),
             "!" => Ok(LogNot),
             "*" => Ok(Deref),
+            "-" => Ok(Neg),
             _ => Err("invalid unary operator".into()),
         }
     }
@@ -234,6 +237,8 @@ impl FromStr for BinOpKind {
             "&" => Ok(And),
             "|" => Ok(Or),
             "^" => Ok(Xor),
+            "&&" => Ok(LogAnd),
+            "||" => Ok(LogOr),
             "<<" => Ok(Shl),
             ">>" => Ok(Shr),
             "==" => Ok(Eq),
@@ -284,6 +289,38 @@ pub enum AssignmentKind {
     ModAssign
 }
 
+impl AssignmentKind {
+    /// Maps a compound assignment operator onto the [`BinOpKind`] it desugars to, e.g.
+    /// `AddAssign -> Add`. Plain `Assign` has no underlying binary operator.
+    pub const fn to_bin_op(&self) -> Option<BinOpKind> {
+        match self {
+            AssignmentKind::Assign => None,
+            AssignmentKind::ShlAssign => Some(BinOpKind::Shl),
+            AssignmentKind::ShrAssign => Some(BinOpKind::Shr),
+            AssignmentKind::AndAssign => Some(BinOpKind::And),
+            AssignmentKind::OrAssign => Some(BinOpKind::Or),
+            AssignmentKind::XorAssign => Some(BinOpKind::Xor),
+            AssignmentKind::AddAssign => Some(BinOpKind::Add),
+            AssignmentKind::SubAssign => Some(BinOpKind::Sub),
+            AssignmentKind::MulAssign => Some(BinOpKind::Mul),
+            AssignmentKind::DivAssign => Some(BinOpKind::Div),
+            AssignmentKind::ModAssign => Some(BinOpKind::Mod),
+        }
+    }
+}
+
+#[cfg(test)]
+mod assignment_kind_test {
+    use super::{AssignmentKind, BinOpKind};
+
+    #[test]
+    fn maps_compound_assignments_to_their_bin_op() {
+        assert_eq!(AssignmentKind::AddAssign.to_bin_op(), Some(BinOpKind::Add));
+        assert_eq!(AssignmentKind::ShlAssign.to_bin_op(), Some(BinOpKind::Shl));
+        assert_eq!(AssignmentKind::Assign.to_bin_op(), None);
+    }
+}
+
 ///  A variable assignment.
 #[derive(Debug, PartialEq)]
 
@@ -314,13 +351,353 @@ impl BinOpKind {
     }
 
     /// Fetch the associativity of this binary operator.
+    ///
+    /// Note that every `BinOpKind` reports `Ltr` here, including the comparison/equality
+    /// operators that are actually non-associative; this method only ever distinguishes
+    /// left-to-right from right-to-left binding. The parser consults [`AssocOp::fixity`] (which
+    /// *can* express non-associativity) rather than this method when deciding how to nest
+    /// repeated operators.
     pub const fn associativity(&self) -> Associativity {
+        Associativity::Ltr
+    }
+
+    /// Returns `true` for the short-circuiting logical operators, `&&` and `||`. These mark a
+    /// node for short-circuit evaluation, so a future codegen stage knows not to evaluate the RHS
+    /// unconditionally.
+    pub const fn is_lazy(&self) -> bool {
+        matches!(self, BinOpKind::LogAnd | BinOpKind::LogOr)
+    }
+
+    /// Returns `true` for the six relational/equality operators (`<`, `>`, `<=`, `>=`, `==`,
+    /// `!=`).
+    pub const fn is_comparison(&self) -> bool {
+        matches!(
+            self,
+            BinOpKind::Lt
+                | BinOpKind::Gt
+                | BinOpKind::Le
+                | BinOpKind::Ge
+                | BinOpKind::Eq
+                | BinOpKind::Ne
+        )
+    }
+
+    /// Fetch the source spelling of this operator, e.g. `BinOpKind::Add` -> `"+"`.
+    pub const fn as_str(&self) -> &'static str {
         match self {
-            _ => Associativity::Ltr,
+            BinOpKind::Add => "+",
+            BinOpKind::Sub => "-",
+            BinOpKind::Mul => "*",
+            BinOpKind::Div => "/",
+            BinOpKind::Mod => "%",
+            BinOpKind::And => "&",
+            BinOpKind::Or => "|",
+            BinOpKind::Xor => "^",
+            BinOpKind::LogAnd => "&&",
+            BinOpKind::LogOr => "||",
+            BinOpKind::Shl => "<<",
+            BinOpKind::Shr => ">>",
+            BinOpKind::Eq => "==",
+            BinOpKind::Ne => "!=",
+            BinOpKind::Lt => "<",
+            BinOpKind::Gt => ">",
+            BinOpKind::Le => "<=",
+            BinOpKind::Ge => ">=",
         }
     }
 }
 
+#[cfg(test)]
+mod bin_op_kind_test {
+    use super::BinOpKind;
+
+    #[test]
+    fn classifies_lazy_and_comparison_operators() {
+        assert!(BinOpKind::LogAnd.is_lazy());
+        assert!(BinOpKind::LogOr.is_lazy());
+        assert!(!BinOpKind::Add.is_lazy());
+
+        assert!(BinOpKind::Lt.is_comparison());
+        assert!(BinOpKind::Eq.is_comparison());
+        assert!(!BinOpKind::Add.is_comparison());
+        assert!(!BinOpKind::LogAnd.is_comparison());
+    }
+
+    #[test]
+    fn as_str_round_trips_through_from_str() {
+        for kind in [BinOpKind::Add, BinOpKind::LogAnd, BinOpKind::Le, BinOpKind::Shr] {
+            assert_eq!(kind.as_str().parse::<BinOpKind>().unwrap(), kind);
+        }
+    }
+}
+
+/// The syntactic position an operator lexeme appears in. Some lexemes are ambiguous in isolation
+/// (`&` is both bitwise-AND and address-of) and can only be resolved by where they sit relative
+/// to their operand(s); `from_token` uses this to disambiguate them.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum OpPosition {
+    /// Before an operand, with no preceding operand, e.g. the `&` in `&x`.
+    Prefix,
+    /// Between two operands, e.g. the `&` in `x & y`.
+    Infix,
+    /// After an operand, e.g. the `++` in `x++`.
+    Postfix,
+}
+
+/// Either a binary or a unary operator, as resolved from a lexeme by [`from_token`].
+#[derive(Debug, PartialEq)]
+pub enum Op {
+    /// A binary (infix) operator.
+    Bin(BinOpKind),
+    /// A unary (prefix or postfix) operator.
+    Un(UnOpKind),
+}
+
+/// Resolves an operator lexeme against its syntactic position, the way rustc's
+/// `AssocOp::from_token` does. This covers the lexemes [`BinOpKind::from_str`] and
+/// [`UnOpKind::from_str`] cannot resolve on their own: `&` is [`BinOpKind::And`] in infix
+/// position but [`UnOpKind::Addr`] in prefix position, `*` is [`BinOpKind::Mul`] vs.
+/// [`UnOpKind::Deref`], and `++`/`--` resolve to [`UnOpKind::PrefixIncr`]/[`UnOpKind::PrefixDecr`]
+/// in prefix position vs. [`UnOpKind::SuffixIncr`]/[`UnOpKind::SuffixDecr`] in postfix position.
+/// This lets a single token stream drive both unary and binary operator construction.
+pub fn from_token(text: &str, position: OpPosition) -> Option<Op> {
+    match (text, position) {
+        ("&", OpPosition::Infix) => Some(Op::Bin(BinOpKind::And)),
+        ("&", OpPosition::Prefix) => Some(Op::Un(UnOpKind::Addr)),
+        ("*", OpPosition::Infix) => Some(Op::Bin(BinOpKind::Mul)),
+        ("*", OpPosition::Prefix) => Some(Op::Un(UnOpKind::Deref)),
+        ("++", OpPosition::Prefix) => Some(Op::Un(UnOpKind::PrefixIncr)),
+        ("++", OpPosition::Postfix) => Some(Op::Un(UnOpKind::SuffixIncr)),
+        ("--", OpPosition::Prefix) => Some(Op::Un(UnOpKind::PrefixDecr)),
+        ("--", OpPosition::Postfix) => Some(Op::Un(UnOpKind::SuffixDecr)),
+        (text, OpPosition::Infix) => BinOpKind::from_str(text).ok().map(Op::Bin),
+        // The catch-all below must still filter by associativity: `UnOpKind::from_str` doesn't
+        // know which position it was called for, so e.g. `*` resolves to `Deref` (prefix-only)
+        // regardless of position, and without this filter it would also be (wrongly) accepted as
+        // a postfix operator.
+        (text, OpPosition::Prefix) => UnOpKind::from_str(text)
+            .ok()
+            .filter(|kind| kind.associativity() == Associativity::Rtl)
+            .map(Op::Un),
+        (text, OpPosition::Postfix) => UnOpKind::from_str(text)
+            .ok()
+            .filter(|kind| kind.associativity() == Associativity::Ltr)
+            .map(Op::Un),
+    }
+}
+
+#[cfg(test)]
+mod from_token_test {
+    use super::{from_token, BinOpKind, Op, OpPosition, UnOpKind};
+
+    #[test]
+    fn resolves_ambiguous_lexemes_by_position() {
+        assert_eq!(from_token("&", OpPosition::Infix), Some(Op::Bin(BinOpKind::And)));
+        assert_eq!(from_token("&", OpPosition::Prefix), Some(Op::Un(UnOpKind::Addr)));
+        assert_eq!(from_token("*", OpPosition::Infix), Some(Op::Bin(BinOpKind::Mul)));
+        assert_eq!(from_token("*", OpPosition::Prefix), Some(Op::Un(UnOpKind::Deref)));
+        assert_eq!(from_token("++", OpPosition::Prefix), Some(Op::Un(UnOpKind::PrefixIncr)));
+        assert_eq!(from_token("++", OpPosition::Postfix), Some(Op::Un(UnOpKind::SuffixIncr)));
+        assert_eq!(from_token("--", OpPosition::Prefix), Some(Op::Un(UnOpKind::PrefixDecr)));
+        assert_eq!(from_token("--", OpPosition::Postfix), Some(Op::Un(UnOpKind::SuffixDecr)));
+    }
+
+    #[test]
+    fn resolves_unambiguous_lexemes() {
+        assert_eq!(from_token("-", OpPosition::Prefix), Some(Op::Un(UnOpKind::Neg)));
+        assert_eq!(from_token("-", OpPosition::Infix), Some(Op::Bin(BinOpKind::Sub)));
+        assert_eq!(from_token("&&", OpPosition::Infix), Some(Op::Bin(BinOpKind::LogAnd)));
+        assert_eq!(from_token("not-an-operator", OpPosition::Infix), None);
+    }
+}
+
+/// Enum representing the fixity of an operator, i.e. whether repeated application of the
+/// operator associates to the left, associates to the right, or does not associate at all.
+///
+/// This is distinct from [`Associativity`], which only ever describes `Ltr`/`Rtl` binding for a
+/// single operator in isolation. `Fixity` is the property [`AssocOp`] exposes, and is what lets
+/// the parser (and later validation passes) tell the difference between an operator that nests
+/// happily with itself (`a + b + c`) and one that must not (`a < b < c`).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Fixity {
+    /// The operator associates to the left, e.g. `a + b + c` parses as `(a + b) + c`.
+    Left,
+    /// The operator associates to the right, e.g. `a = b = c` parses as `a = (b = c)`.
+    Right,
+    /// The operator does not associate with itself at all, e.g. `a < b < c` is not valid.
+    None,
+}
+
+/// A unified operator abstraction mirroring rustc's `util::parser::AssocOp`.
+///
+/// Precedence and associativity are otherwise spread across [`BinOpKind::precedence`],
+/// [`BinOpKind::associativity`], [`UnOpKind::precedence`] and [`AssignmentKind`].
+/// `AssocOp` folds binary operators and assignment (plain and compound) into one
+/// precedence-bearing type that answers both "what binds tighter" and "which way". Its
+/// precedence values match [`BinOpKind::precedence`]; the parser in [`crate::parser`] reads
+/// precedence from [`BinOpKind::precedence`] and fixity from [`AssocOp::fixity`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AssocOp {
+    /// The addition operator, `+`.
+    Add,
+    /// The subtraction operator, `-`.
+    Subtract,
+    /// The multiplication operator, `*`.
+    Multiply,
+    /// The division operator, `/`.
+    Divide,
+    /// The modulo operator, `%`.
+    Modulus,
+    /// The logical AND operator, `&&`.
+    LAnd,
+    /// The logical OR operator, `||`.
+    LOr,
+    /// The bitwise XOR operator, `^`.
+    BitXor,
+    /// The bitwise AND operator, `&`.
+    BitAnd,
+    /// The bitwise OR operator, `|`.
+    BitOr,
+    /// The bitwise left shift operator, `<<`.
+    Shl,
+    /// The bitwise right shift operator, `>>`.
+    Shr,
+    /// The equality operator, `==`.
+    Eq,
+    /// The less-than operator, `<`.
+    Lt,
+    /// The less-than-or-equal operator, `<=`.
+    Le,
+    /// The inequality operator, `!=`.
+    Ne,
+    /// The greater-than-or-equal operator, `>=`.
+    Ge,
+    /// The greater-than operator, `>`.
+    Gt,
+    /// The plain assignment operator, `=`.
+    Assign,
+    /// A compound assignment operator, e.g. `+=`, carrying the binary operator it desugars to.
+    AssignOp(BinOpKind),
+}
+
+impl AssocOp {
+    /// Build the `AssocOp` corresponding to a [`BinOpKind`].
+    pub const fn from_bin_op(kind: BinOpKind) -> AssocOp {
+        match kind {
+            BinOpKind::Add => AssocOp::Add,
+            BinOpKind::Sub => AssocOp::Subtract,
+            BinOpKind::Mul => AssocOp::Multiply,
+            BinOpKind::Div => AssocOp::Divide,
+            BinOpKind::Mod => AssocOp::Modulus,
+            BinOpKind::And => AssocOp::BitAnd,
+            BinOpKind::Or => AssocOp::BitOr,
+            BinOpKind::Xor => AssocOp::BitXor,
+            BinOpKind::LogAnd => AssocOp::LAnd,
+            BinOpKind::LogOr => AssocOp::LOr,
+            BinOpKind::Shl => AssocOp::Shl,
+            BinOpKind::Shr => AssocOp::Shr,
+            BinOpKind::Eq => AssocOp::Eq,
+            BinOpKind::Ne => AssocOp::Ne,
+            BinOpKind::Lt => AssocOp::Lt,
+            BinOpKind::Gt => AssocOp::Gt,
+            BinOpKind::Le => AssocOp::Le,
+            BinOpKind::Ge => AssocOp::Ge,
+        }
+    }
+
+    /// Recover the underlying [`BinOpKind`], if any. Plain assignment (`=`) has none.
+    pub const fn to_bin_op(&self) -> Option<BinOpKind> {
+        match self {
+            AssocOp::Add => Some(BinOpKind::Add),
+            AssocOp::Subtract => Some(BinOpKind::Sub),
+            AssocOp::Multiply => Some(BinOpKind::Mul),
+            AssocOp::Divide => Some(BinOpKind::Div),
+            AssocOp::Modulus => Some(BinOpKind::Mod),
+            AssocOp::BitAnd => Some(BinOpKind::And),
+            AssocOp::BitOr => Some(BinOpKind::Or),
+            AssocOp::BitXor => Some(BinOpKind::Xor),
+            AssocOp::LAnd => Some(BinOpKind::LogAnd),
+            AssocOp::LOr => Some(BinOpKind::LogOr),
+            AssocOp::Shl => Some(BinOpKind::Shl),
+            AssocOp::Shr => Some(BinOpKind::Shr),
+            AssocOp::Eq => Some(BinOpKind::Eq),
+            AssocOp::Ne => Some(BinOpKind::Ne),
+            AssocOp::Lt => Some(BinOpKind::Lt),
+            AssocOp::Gt => Some(BinOpKind::Gt),
+            AssocOp::Le => Some(BinOpKind::Le),
+            AssocOp::Ge => Some(BinOpKind::Ge),
+            AssocOp::AssignOp(kind) => Some(*kind),
+            AssocOp::Assign => None,
+        }
+    }
+
+    /// Fetch the precedence of this operator, on the same scale as [`BinOpKind::precedence`]
+    /// (lower value binds tighter). Assignment binds loosest of all.
+    pub const fn precedence(&self) -> usize {
+        match self {
+            AssocOp::Multiply | AssocOp::Divide | AssocOp::Modulus => 3,
+            AssocOp::Add | AssocOp::Subtract => 4,
+            AssocOp::Shl | AssocOp::Shr => 5,
+            AssocOp::Lt | AssocOp::Gt | AssocOp::Le | AssocOp::Ge => 6,
+            AssocOp::Eq | AssocOp::Ne => 7,
+            AssocOp::BitAnd => 8,
+            AssocOp::BitXor => 9,
+            AssocOp::BitOr => 10,
+            AssocOp::LAnd => 11,
+            AssocOp::LOr => 12,
+            AssocOp::Assign | AssocOp::AssignOp(_) => 13,
+        }
+    }
+
+    /// Fetch the fixity of this operator.
+    pub const fn fixity(&self) -> Fixity {
+        match self {
+            AssocOp::Lt | AssocOp::Gt | AssocOp::Le | AssocOp::Ge | AssocOp::Eq | AssocOp::Ne => {
+                Fixity::None
+            }
+            AssocOp::Assign | AssocOp::AssignOp(_) => Fixity::Right,
+            _ => Fixity::Left,
+        }
+    }
+}
+
+#[cfg(test)]
+mod assoc_op_test {
+    use super::{AssocOp, BinOpKind, Fixity};
+
+    #[test]
+    fn round_trips_through_bin_op_kind() {
+        for kind in [
+            BinOpKind::Add,
+            BinOpKind::Lt,
+            BinOpKind::LogAnd,
+            BinOpKind::Shl,
+        ] {
+            assert_eq!(AssocOp::from_bin_op(kind).to_bin_op(), Some(kind));
+        }
+    }
+
+    #[test]
+    fn assign_has_no_underlying_bin_op() {
+        assert_eq!(AssocOp::Assign.to_bin_op(), None);
+        assert_eq!(AssocOp::AssignOp(BinOpKind::Add).to_bin_op(), Some(BinOpKind::Add));
+    }
+
+    #[test]
+    fn fixity_matches_real_rust_semantics() {
+        assert_eq!(AssocOp::Add.fixity(), Fixity::Left);
+        assert_eq!(AssocOp::Assign.fixity(), Fixity::Right);
+        assert_eq!(AssocOp::Lt.fixity(), Fixity::None);
+        assert_eq!(AssocOp::Eq.fixity(), Fixity::None);
+    }
+
+    #[test]
+    fn assign_binds_loosest() {
+        assert!(AssocOp::Assign.precedence() > AssocOp::LOr.precedence());
+        assert!(AssocOp::LOr.precedence() > AssocOp::Add.precedence());
+    }
+}
+
 /// A binary expression.
 #[derive(Debug, PartialEq)]
 pub struct BinOp {
@@ -332,6 +709,21 @@ pub struct BinOp {
     pub rhs: Box<Expr>,
     /// The kind of binary expression.
     pub kind: BinOpKind,
+    /// The span of the combined expression.
+    pub span: Span,
+}
+
+/// A unary expression, e.g. `-x`, `x++`, `arr[3]`.
+#[derive(Debug, PartialEq)]
+pub struct UnOp {
+    /// The ID of this node in the AST.
+    pub id: usize,
+    /// The operand the operator is applied to.
+    pub operand: Box<Expr>,
+    /// The kind of unary expression.
+    pub kind: UnOpKind,
+    /// The span of the combined expression.
+    pub span: Span,
 }
 
 /// An enum representing variable mutability.
@@ -384,10 +776,26 @@ pub enum Expr {
     Ident(Ident),
     /// A binary operation expression.
     BinOp(BinOp),
+    /// A unary operation expression.
+    UnOp(UnOp),
     /// A block (e.g. `{ /* ... */ }`).
     Block(Box<Block>),
 }
 
+impl Expr {
+    /// Returns the span of this expression. `Expr::Block` has no span of its own yet, since
+    /// `Block`/`Stmt` don't currently track spans.
+    pub(crate) fn span(&self) -> &Span {
+        match self {
+            Expr::Literal(lit) => &lit.span,
+            Expr::Ident(ident) => &ident.span,
+            Expr::BinOp(bin_op) => &bin_op.span,
+            Expr::UnOp(un_op) => &un_op.span,
+            Expr::Block(_) => unreachable!("`Block` expressions have no span yet"),
+        }
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub struct Block {
     /// The list of statements in the block.
@@ -453,7 +861,7 @@ struct ASTValidator {
 impl Default for ASTValidator {
     fn default() -> ASTValidator {
         ASTValidator {
-            passes: vec![validate_symbols, validate_types],
+            passes: vec![validate_symbols, validate_types, validate_no_chained_comparisons],
         }
     }
 }
@@ -0,0 +1,217 @@
+//! AST lowering passes, run ahead of type-checking/codegen to desugar sugared constructs into a
+//! canonical form later stages only need to handle once.
+
+use crate::{
+    Assignment, AssignmentKind, BinOp, Expr, Ident, Loop, Span, Stmt, StmtKind, UnOpKind, AST,
+};
+
+/// Rewrites every compound assignment statement (e.g. `x += 1`) into a plain assignment whose
+/// value is the corresponding [`BinOp`] (`x = x + 1`), using [`AssignmentKind::to_bin_op`]. This
+/// mirrors how rustc's `AssocOp::AssignOp` maps onto the underlying binary operator, and lets
+/// later type-checking and codegen stages handle only one assignment form.
+///
+/// Top-level statements and the statements of loop bodies are visited; statements inside a block
+/// expression (`Expr::Block`) are not, so compound assignments there are left as they are.
+///
+/// Existing node `id`s are left untouched; the synthesized `BinOp`/`Ident` nodes are given fresh
+/// ids continuing on from the highest id already present in the AST.
+pub fn lower_compound_assignments(ast: &mut AST) {
+    let mut next_id = max_id(ast) + 1;
+    for stmt in &mut ast.stmts {
+        lower_stmt(stmt, &mut next_id);
+    }
+}
+
+fn lower_stmt(stmt: &mut Stmt, next_id: &mut usize) {
+    match &mut stmt.kind {
+        StmtKind::Assignment(assignment) => lower_assignment(assignment, next_id),
+        StmtKind::Loop(loop_) => lower_loop(loop_, next_id),
+        StmtKind::Declaration(_) => {}
+    }
+}
+
+fn lower_loop(loop_: &mut Loop, next_id: &mut usize) {
+    for stmt in &mut loop_.block.stmts {
+        lower_stmt(stmt, next_id);
+    }
+}
+
+fn lower_assignment(assignment: &mut Assignment, next_id: &mut usize) {
+    let kind = match assignment.kind.to_bin_op() {
+        Some(kind) => kind,
+        None => return,
+    };
+
+    let target = Ident {
+        id: alloc_id(next_id),
+        name: assignment.ident.name.clone(),
+        span: Span(assignment.ident.span.0, assignment.ident.span.1),
+    };
+
+    // Placeholder swapped back out immediately below; only needed to move `assignment.value` out
+    // from behind the `&mut` without a temporary clone of the whole expression tree.
+    let value = std::mem::replace(&mut assignment.value, Expr::Ident(placeholder_ident()));
+    // `Expr::Block` has no span of its own yet (see `Expr::span`), so a block-valued compound
+    // assignment falls back to ending the synthesized span at the target identifier.
+    let value_end = match &value {
+        Expr::Block(_) => target.span.1,
+        other => other.span().1,
+    };
+    let span = Span(target.span.0, value_end);
+
+    assignment.value = Expr::BinOp(BinOp {
+        id: alloc_id(next_id),
+        lhs: Box::new(Expr::Ident(target)),
+        rhs: Box::new(value),
+        kind,
+        span,
+    });
+    assignment.kind = AssignmentKind::Assign;
+}
+
+fn placeholder_ident() -> Ident {
+    Ident {
+        id: 0,
+        name: String::new(),
+        span: Span(0, 0),
+    }
+}
+
+fn alloc_id(next_id: &mut usize) -> usize {
+    let id = *next_id;
+    *next_id += 1;
+    id
+}
+
+/// Finds the highest node `id` already present in the AST, so synthesized nodes can be given ids
+/// that don't collide with existing ones.
+fn max_id(ast: &AST) -> usize {
+    ast.stmts.iter().map(max_id_stmt).max().unwrap_or(0)
+}
+
+fn max_id_stmt(stmt: &Stmt) -> usize {
+    let inner = match &stmt.kind {
+        StmtKind::Declaration(decls) => decls
+            .iter()
+            .map(|decl| decl.ident.id.max(max_id_expr(&decl.value)))
+            .max()
+            .unwrap_or(0),
+        StmtKind::Assignment(assignment) => assignment
+            .ident
+            .id
+            .max(max_id_expr(&assignment.value)),
+        StmtKind::Loop(loop_) => loop_
+            .block
+            .stmts
+            .iter()
+            .map(max_id_stmt)
+            .max()
+            .unwrap_or(loop_.block.id)
+            .max(loop_.block.id)
+            .max(loop_.id),
+    };
+    stmt.id.max(inner)
+}
+
+fn max_id_expr(expr: &Expr) -> usize {
+    match expr {
+        Expr::Literal(lit) => lit.id,
+        Expr::Ident(ident) => ident.id,
+        Expr::BinOp(bin_op) => bin_op.id.max(max_id_expr(&bin_op.lhs)).max(max_id_expr(&bin_op.rhs)),
+        Expr::UnOp(un_op) => {
+            let args_max = match &un_op.kind {
+                UnOpKind::Call(args) => args.iter().map(|arg| arg.id).max().unwrap_or(0),
+                _ => 0,
+            };
+            un_op.id.max(max_id_expr(&un_op.operand)).max(args_max)
+        }
+        Expr::Block(block) => block
+            .stmts
+            .iter()
+            .map(max_id_stmt)
+            .max()
+            .unwrap_or(block.id)
+            .max(block.id),
+    }
+}
+
+#[cfg(test)]
+mod lower_compound_assignments_test {
+    use super::lower_compound_assignments;
+    use crate::{
+        Assignment, AssignmentKind, Block, Expr, Ident, Literal, LiteralKind, Span, Stmt,
+        StmtKind, AST,
+    };
+
+    fn ident(id: usize, name: &str) -> Ident {
+        Ident {
+            id,
+            name: name.to_string(),
+            span: Span(0, 1),
+        }
+    }
+
+    fn int_literal(id: usize, n: i64) -> Expr {
+        Expr::Literal(Literal {
+            id,
+            kind: LiteralKind::Int(n),
+            span: Span(0, 1),
+        })
+    }
+
+    #[test]
+    fn desugars_compound_assignment_into_a_plain_assignment_of_a_bin_op() {
+        // x += 1
+        let mut ast = AST {
+            stmts: vec![Stmt {
+                id: 0,
+                kind: StmtKind::Assignment(Assignment {
+                    ident: ident(1, "x"),
+                    value: int_literal(2, 1),
+                    kind: AssignmentKind::AddAssign,
+                }),
+            }],
+            modules: vec![],
+        };
+
+        lower_compound_assignments(&mut ast);
+
+        match &ast.stmts[0].kind {
+            StmtKind::Assignment(assignment) => {
+                assert_eq!(assignment.kind, AssignmentKind::Assign);
+                match &assignment.value {
+                    Expr::BinOp(bin_op) => {
+                        assert_eq!(bin_op.kind, crate::BinOpKind::Add);
+                        assert!(matches!(*bin_op.lhs, Expr::Ident(_)));
+                        assert!(matches!(*bin_op.rhs, Expr::Literal(_)));
+                    }
+                    other => panic!("expected the desugared value to be a BinOp, got {:?}", other),
+                }
+            }
+            other => panic!("expected an Assignment, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn does_not_panic_lowering_a_block_valued_compound_assignment() {
+        // x += { }
+        let mut ast = AST {
+            stmts: vec![Stmt {
+                id: 0,
+                kind: StmtKind::Assignment(Assignment {
+                    ident: ident(1, "x"),
+                    value: Expr::Block(Box::new(Block { stmts: vec![], id: 2 })),
+                    kind: AssignmentKind::AddAssign,
+                }),
+            }],
+            modules: vec![],
+        };
+
+        lower_compound_assignments(&mut ast);
+
+        match &ast.stmts[0].kind {
+            StmtKind::Assignment(assignment) => assert_eq!(assignment.kind, AssignmentKind::Assign),
+            other => panic!("expected an Assignment, got {:?}", other),
+        }
+    }
+}
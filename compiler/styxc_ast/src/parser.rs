@@ -0,0 +1,306 @@
+//! A precedence-climbing expression parser.
+//!
+//! [`BinOpKind::precedence`] and [`AssocOp::fixity`] describe the operator tables, but nothing in
+//! the crate previously assembled a correctly-nested [`Expr`] tree from them. [`parse_expr`] does
+//! that: it climbs the precedence tables to fold a flat stream of [`Token`]s into a single `Expr`.
+//!
+//! Tokens carry plain operator text rather than a pre-resolved kind, since the crate has no
+//! separate lexer yet; operator lexemes are resolved against their syntactic position via
+//! [`crate::from_token`], which is what lets `&`/`*`/`++`/`--` mean different things as a prefix,
+//! infix, or postfix operator.
+
+use std::error::Error;
+
+use crate::{
+    from_token, AssocOp, BinOp, BinOpKind, Expr, Fixity, Ident, Literal, LiteralKind, Op,
+    OpPosition, ParenArgument, Span, UnOp, UnOpKind,
+};
+
+/// A single element of the flat token stream consumed by [`parse_expr`].
+#[derive(Debug, PartialEq)]
+pub enum Token {
+    /// A literal operand.
+    Literal(LiteralKind, Span),
+    /// An identifier operand.
+    Ident(String, Span),
+    /// An operator lexeme, e.g. `"+"`, `"&&"`, `"++"`, `"[3]"`.
+    Op(String, Span),
+    /// A parenthesized call argument list, carrying the IDs of the already-parsed argument
+    /// identifiers.
+    Call(Vec<usize>, Span),
+}
+
+/// Parses a single expression from `tokens`, climbing the operator precedence tables to build a
+/// correctly-nested [`Expr`] tree.
+///
+/// Fresh AST node `id`s are assigned starting from `0`, and each produced node's [`Span`] is the
+/// combination of its children's spans. Returns an error instead of panicking if `tokens` doesn't
+/// contain a valid expression (e.g. it's empty, or ends mid-operator).
+pub fn parse_expr(tokens: &[Token]) -> Result<Expr, Box<dyn Error>> {
+    let mut parser = Parser {
+        tokens,
+        pos: 0,
+        next_id: 0,
+    };
+    parser.parse_expr_bp(usize::MAX)
+}
+
+/// Resolves a prefix unary operator lexeme, i.e. one written before its operand, via
+/// [`from_token`]. This is what disambiguates e.g. `++` (-> [`UnOpKind::PrefixIncr`]) from its
+/// postfix counterpart, and `&`/`*` from their binary-operator counterparts.
+fn prefix_unop(text: &str) -> Option<UnOpKind> {
+    match from_token(text, OpPosition::Prefix) {
+        Some(Op::Un(kind)) => Some(kind),
+        _ => None,
+    }
+}
+
+/// Resolves a postfix unary operator lexeme, i.e. one written after its operand, via
+/// [`from_token`].
+fn postfix_unop(text: &str) -> Option<UnOpKind> {
+    match from_token(text, OpPosition::Postfix) {
+        Some(Op::Un(kind)) => Some(kind),
+        _ => None,
+    }
+}
+
+/// Resolves an infix binary operator lexeme via [`from_token`].
+fn infix_binop(text: &str) -> Option<BinOpKind> {
+    match from_token(text, OpPosition::Infix) {
+        Some(Op::Bin(kind)) => Some(kind),
+        _ => None,
+    }
+}
+
+/// Cursor over a token stream, used to implement precedence-climbing parsing.
+struct Parser<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+    next_id: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn next_id(&mut self) -> usize {
+        let id = self.next_id;
+        self.next_id += 1;
+        id
+    }
+
+    fn peek(&self) -> Option<&'a Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn bump(&mut self) -> Option<&'a Token> {
+        let tok = self.tokens.get(self.pos);
+        if tok.is_some() {
+            self.pos += 1;
+        }
+        tok
+    }
+
+    /// Parse a primary operand: an optional unary prefix operator, a literal/identifier, then any
+    /// trailing postfix operators (`++`, `--`, `[n]`, a call argument list).
+    fn parse_primary(&mut self) -> Result<Expr, Box<dyn Error>> {
+        // Unary prefix operators bind tighter than any infix operator, so they're peeled off
+        // before the primary operand is parsed.
+        if let Some(Token::Op(text, op_span)) = self.peek() {
+            if let Some(kind) = prefix_unop(text) {
+                let op_span = Span(op_span.0, op_span.1);
+                self.bump();
+                let operand = self.parse_primary()?;
+                let span = Span(op_span.0, operand.span().1);
+                return Ok(Expr::UnOp(UnOp {
+                    id: self.next_id(),
+                    operand: Box::new(operand),
+                    kind,
+                    span,
+                }));
+            }
+        }
+
+        let mut expr = match self.bump() {
+            Some(Token::Literal(kind, span)) => Expr::Literal(Literal {
+                id: self.next_id(),
+                kind: clone_literal_kind(kind),
+                span: Span(span.0, span.1),
+            }),
+            Some(Token::Ident(name, span)) => Expr::Ident(Ident {
+                id: self.next_id(),
+                name: name.clone(),
+                span: Span(span.0, span.1),
+            }),
+            tok => return Err(format!("expected an operand, found {:?}", tok).into()),
+        };
+
+        // Postfix operators are consumed left-to-right after the operand, at their own
+        // (tighter-than-infix) precedence level.
+        loop {
+            match self.peek() {
+                Some(Token::Op(text, op_span)) if postfix_unop(text).is_some() => {
+                    let kind = postfix_unop(text).unwrap();
+                    let span = Span(expr.span().0, op_span.1);
+                    self.bump();
+                    expr = Expr::UnOp(UnOp {
+                        id: self.next_id(),
+                        operand: Box::new(expr),
+                        kind,
+                        span,
+                    });
+                }
+                Some(Token::Call(idents, call_span)) => {
+                    let idents = idents.clone();
+                    let call_span = Span(call_span.0, call_span.1);
+                    self.bump();
+                    let span = Span(expr.span().0, call_span.1);
+                    let args = idents
+                        .into_iter()
+                        .map(|ident| ParenArgument {
+                            id: self.next_id(),
+                            ident,
+                        })
+                        .collect();
+                    expr = Expr::UnOp(UnOp {
+                        id: self.next_id(),
+                        operand: Box::new(expr),
+                        kind: UnOpKind::Call(args),
+                        span,
+                    });
+                }
+                _ => break,
+            }
+        }
+
+        Ok(expr)
+    }
+
+    /// Parse an expression, consuming infix operators whose precedence binds at least as tightly
+    /// as `max_prec` allows (lower numeric value binds tighter, per [`BinOpKind::precedence`]).
+    fn parse_expr_bp(&mut self, max_prec: usize) -> Result<Expr, Box<dyn Error>> {
+        let mut lhs = self.parse_primary()?;
+
+        while let Some(Token::Op(text, _)) = self.peek() {
+            let kind = match infix_binop(text) {
+                Some(kind) => kind,
+                None => break,
+            };
+            let prec = kind.precedence();
+            if prec > max_prec {
+                break;
+            }
+            self.bump();
+
+            // `Fixity::None` (the non-associative comparison/equality operators) is treated the
+            // same as `Left` here: the tree is still built left-nested so parsing always
+            // succeeds, and `passes::validate_no_chained_comparisons` is what actually rejects a
+            // chain like `a < b < c`.
+            let next_max = match AssocOp::from_bin_op(kind).fixity() {
+                Fixity::Left | Fixity::None => prec - 1,
+                Fixity::Right => prec,
+            };
+            let rhs = self.parse_expr_bp(next_max)?;
+
+            let span = Span(lhs.span().0, rhs.span().1);
+            lhs = Expr::BinOp(BinOp {
+                id: self.next_id(),
+                lhs: Box::new(lhs),
+                rhs: Box::new(rhs),
+                kind,
+                span,
+            });
+        }
+
+        Ok(lhs)
+    }
+}
+
+fn clone_literal_kind(kind: &LiteralKind) -> LiteralKind {
+    match kind {
+        LiteralKind::Int(v) => LiteralKind::Int(*v),
+        LiteralKind::Float(v) => LiteralKind::Float(*v),
+        LiteralKind::String(v) => LiteralKind::String(v.clone()),
+        LiteralKind::Char(v) => LiteralKind::Char(*v),
+        LiteralKind::Bool(v) => LiteralKind::Bool(*v),
+    }
+}
+
+#[cfg(test)]
+mod parse_expr_test {
+    use super::{parse_expr, Token};
+    use crate::{BinOpKind, Expr, LiteralKind, Span, UnOpKind};
+
+    fn int(n: i64, span: Span) -> Token {
+        Token::Literal(LiteralKind::Int(n), span)
+    }
+
+    fn op(text: &str, span: Span) -> Token {
+        Token::Op(text.to_string(), span)
+    }
+
+    #[test]
+    fn respects_precedence() {
+        // 1 + 2 * 3
+        let expr = parse_expr(&[
+            int(1, Span(0, 1)),
+            op("+", Span(1, 2)),
+            int(2, Span(2, 3)),
+            op("*", Span(3, 4)),
+            int(3, Span(4, 5)),
+        ])
+        .unwrap();
+
+        match expr {
+            Expr::BinOp(bin_op) => {
+                assert_eq!(bin_op.kind, BinOpKind::Add);
+                assert!(matches!(*bin_op.lhs, Expr::Literal(_)));
+                match *bin_op.rhs {
+                    Expr::BinOp(rhs) => assert_eq!(rhs.kind, BinOpKind::Mul),
+                    other => panic!("expected `2 * 3` to nest as the rhs, got {:?}", other),
+                }
+            }
+            other => panic!("expected a top-level `+`, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parses_prefix_negation_without_panicking() {
+        // -5
+        let expr = parse_expr(&[op("-", Span(0, 1)), int(5, Span(1, 2))]).unwrap();
+        match expr {
+            Expr::UnOp(un_op) => assert_eq!(un_op.kind, UnOpKind::Neg),
+            other => panic!("expected a unary negation, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parses_prefix_increment_without_panicking() {
+        // ++x
+        let expr = parse_expr(&[
+            op("++", Span(0, 2)),
+            Token::Ident("x".to_string(), Span(2, 3)),
+        ])
+        .unwrap();
+        match expr {
+            Expr::UnOp(un_op) => assert_eq!(un_op.kind, UnOpKind::PrefixIncr),
+            other => panic!("expected a prefix increment, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parses_postfix_increment() {
+        // x++
+        let expr = parse_expr(&[
+            Token::Ident("x".to_string(), Span(0, 1)),
+            op("++", Span(1, 3)),
+        ])
+        .unwrap();
+        match expr {
+            Expr::UnOp(un_op) => assert_eq!(un_op.kind, UnOpKind::SuffixIncr),
+            other => panic!("expected a postfix increment, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn reports_an_error_instead_of_panicking_on_missing_operand() {
+        assert!(parse_expr(&[op("+", Span(0, 1))]).is_err());
+    }
+}
@@ -0,0 +1,369 @@
+//! AST validation passes, run by `ASTValidator`.
+
+use std::error::Error;
+
+use crate::{BinOp, BinOpKind, Context, Expr, LiteralKind, Mutability, Span, Stmt, StmtKind, Var, AST};
+
+/// Walks the AST checking that every assignment targets a variable that has actually been
+/// declared, and that the variable being assigned to is mutable.
+pub(crate) fn validate_symbols(ast: &AST) -> Result<(), Box<dyn Error>> {
+    let mut ctx = Context { vars: vec![] };
+    for stmt in &ast.stmts {
+        validate_stmt_symbols(stmt, &mut ctx)?;
+    }
+    Ok(())
+}
+
+fn validate_stmt_symbols(stmt: &Stmt, ctx: &mut Context) -> Result<(), Box<dyn Error>> {
+    match &stmt.kind {
+        StmtKind::Declaration(decls) => {
+            for decl in decls {
+                ctx.vars.push(Var {
+                    ident: decl.ident.id,
+                    mutability: decl.mutability,
+                });
+            }
+        }
+        StmtKind::Assignment(assignment) => match ctx.vars.iter().find(|v| v.ident == assignment.ident.id) {
+            None => {
+                return Err(format!(
+                    "assignment to undeclared variable `{}`",
+                    assignment.ident.name
+                )
+                .into())
+            }
+            Some(var) if var.mutability != Mutability::Mutable => {
+                return Err(format!(
+                    "cannot assign to immutable variable `{}`",
+                    assignment.ident.name
+                )
+                .into())
+            }
+            Some(_) => {}
+        },
+        StmtKind::Loop(loop_) => {
+            for stmt in &loop_.block.stmts {
+                validate_stmt_symbols(stmt, ctx)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// A crude value type, inferred structurally. The crate has no dedicated type-checker yet, so
+/// this only tracks what can be read directly off a literal or a comparison/logical result; any
+/// other binary operator is taken to have the type of its left operand.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum ValueType {
+    Int,
+    Float,
+    String,
+    Char,
+    Bool,
+}
+
+/// Infer the [`ValueType`] of an expression, where possible. Returns `None` for expressions
+/// (such as bare identifiers) whose type can't be determined without a symbol table.
+fn infer_type(expr: &Expr) -> Option<ValueType> {
+    match expr {
+        Expr::Literal(lit) => Some(match lit.kind {
+            LiteralKind::Int(_) => ValueType::Int,
+            LiteralKind::Float(_) => ValueType::Float,
+            LiteralKind::String(_) => ValueType::String,
+            LiteralKind::Char(_) => ValueType::Char,
+            LiteralKind::Bool(_) => ValueType::Bool,
+        }),
+        Expr::BinOp(bin_op) => infer_bin_op_type(bin_op),
+        _ => None,
+    }
+}
+
+fn infer_bin_op_type(bin_op: &BinOp) -> Option<ValueType> {
+    if bin_op.kind.is_comparison() || bin_op.kind.is_lazy() {
+        return Some(ValueType::Bool);
+    }
+    infer_type(&bin_op.lhs)
+}
+
+/// Walks the AST checking that binary operators are applied to compatible operand types, where
+/// those types can be inferred.
+pub(crate) fn validate_types(ast: &AST) -> Result<(), Box<dyn Error>> {
+    for stmt in &ast.stmts {
+        validate_stmt_types(stmt)?;
+    }
+    Ok(())
+}
+
+fn validate_stmt_types(stmt: &Stmt) -> Result<(), Box<dyn Error>> {
+    match &stmt.kind {
+        StmtKind::Declaration(decls) => {
+            for decl in decls {
+                validate_expr_types(&decl.value)?;
+            }
+        }
+        StmtKind::Assignment(assignment) => validate_expr_types(&assignment.value)?,
+        StmtKind::Loop(loop_) => {
+            for stmt in &loop_.block.stmts {
+                validate_stmt_types(stmt)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn validate_expr_types(expr: &Expr) -> Result<(), Box<dyn Error>> {
+    if let Expr::BinOp(bin_op) = expr {
+        validate_expr_types(&bin_op.lhs)?;
+        validate_expr_types(&bin_op.rhs)?;
+
+        if let (Some(lhs_ty), Some(rhs_ty)) = (infer_type(&bin_op.lhs), infer_type(&bin_op.rhs)) {
+            if bin_op.kind.is_comparison() && lhs_ty != rhs_ty {
+                return Err(format!(
+                    "cannot apply `{}` to `{:?}` and `{:?}` at {:?}",
+                    bin_op.kind.as_str(),
+                    lhs_ty,
+                    rhs_ty,
+                    bin_op.span
+                )
+                .into());
+            }
+            if bin_op.kind.is_lazy() && (lhs_ty != ValueType::Bool || rhs_ty != ValueType::Bool) {
+                return Err(format!(
+                    "operands of `{}` must be `bool` at {:?}",
+                    bin_op.kind.as_str(),
+                    bin_op.span
+                )
+                .into());
+            }
+            if matches!(
+                bin_op.kind,
+                BinOpKind::Shl | BinOpKind::Shr | BinOpKind::And | BinOpKind::Or | BinOpKind::Xor
+            ) && (lhs_ty != ValueType::Int || rhs_ty != ValueType::Int)
+            {
+                return Err(format!(
+                    "operands of `{}` must be integers at {:?}",
+                    bin_op.kind.as_str(),
+                    bin_op.span
+                )
+                .into());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Walks the AST checking that comparison/equality operators (`<`, `>`, `<=`, `>=`, `==`, `!=`)
+/// are not chained directly, e.g. `a < b < c`, which (as in real Rust) is ambiguous since these
+/// operators are non-associative (see `AssocOp::fixity`).
+pub(crate) fn validate_no_chained_comparisons(ast: &AST) -> Result<(), Box<dyn Error>> {
+    for stmt in &ast.stmts {
+        validate_stmt_no_chained_comparisons(stmt)?;
+    }
+    Ok(())
+}
+
+fn validate_stmt_no_chained_comparisons(stmt: &Stmt) -> Result<(), Box<dyn Error>> {
+    match &stmt.kind {
+        StmtKind::Declaration(decls) => {
+            for decl in decls {
+                validate_expr_no_chained_comparisons(&decl.value)?;
+            }
+        }
+        StmtKind::Assignment(assignment) => validate_expr_no_chained_comparisons(&assignment.value)?,
+        StmtKind::Loop(loop_) => {
+            for stmt in &loop_.block.stmts {
+                validate_stmt_no_chained_comparisons(stmt)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn validate_expr_no_chained_comparisons(expr: &Expr) -> Result<(), Box<dyn Error>> {
+    if let Expr::BinOp(bin_op) = expr {
+        if bin_op.kind.is_comparison() {
+            if let Some(span) = chained_comparison_span(&bin_op.lhs) {
+                return Err(format!("comparison operators cannot be chained: {:?}", span).into());
+            }
+            if let Some(span) = chained_comparison_span(&bin_op.rhs) {
+                return Err(format!("comparison operators cannot be chained: {:?}", span).into());
+            }
+        }
+        validate_expr_no_chained_comparisons(&bin_op.lhs)?;
+        validate_expr_no_chained_comparisons(&bin_op.rhs)?;
+    }
+    Ok(())
+}
+
+/// Returns the span of `expr` if it is itself a comparison/equality `BinOp`, i.e. a direct child
+/// that would make the parent a chained comparison. `Expr::Block` is treated as an intervening
+/// boundary, since the AST has no dedicated grouping/parenthesis node.
+fn chained_comparison_span(expr: &Expr) -> Option<&Span> {
+    match expr {
+        Expr::BinOp(bin_op) if bin_op.kind.is_comparison() => Some(&bin_op.span),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod validate_symbols_test {
+    use super::validate_symbols;
+    use crate::{Assignment, AssignmentKind, Declaration, Expr, Ident, Literal, LiteralKind, Mutability, Span, Stmt, StmtKind, AST};
+
+    fn ident(id: usize, name: &str) -> Ident {
+        Ident {
+            id,
+            name: name.to_string(),
+            span: Span(0, 0),
+        }
+    }
+
+    fn int_literal(id: usize, n: i64) -> Expr {
+        Expr::Literal(Literal {
+            id,
+            kind: LiteralKind::Int(n),
+            span: Span(0, 0),
+        })
+    }
+
+    #[test]
+    fn rejects_assignment_to_undeclared_variable() {
+        let ast = AST {
+            stmts: vec![Stmt {
+                id: 0,
+                kind: StmtKind::Assignment(Assignment {
+                    ident: ident(1, "x"),
+                    value: int_literal(2, 1),
+                    kind: AssignmentKind::Assign,
+                }),
+            }],
+            modules: vec![],
+        };
+
+        assert!(validate_symbols(&ast).is_err());
+    }
+
+    #[test]
+    fn rejects_assignment_to_immutable_variable() {
+        let ast = AST {
+            stmts: vec![
+                Stmt {
+                    id: 0,
+                    kind: StmtKind::Declaration(vec![Declaration {
+                        ident: ident(1, "x"),
+                        mutability: Mutability::Immutable,
+                        value: int_literal(2, 1),
+                    }]),
+                },
+                Stmt {
+                    id: 3,
+                    kind: StmtKind::Assignment(Assignment {
+                        ident: ident(1, "x"),
+                        value: int_literal(4, 2),
+                        kind: AssignmentKind::Assign,
+                    }),
+                },
+            ],
+            modules: vec![],
+        };
+
+        assert!(validate_symbols(&ast).is_err());
+    }
+
+    #[test]
+    fn accepts_assignment_to_mutable_variable() {
+        let ast = AST {
+            stmts: vec![
+                Stmt {
+                    id: 0,
+                    kind: StmtKind::Declaration(vec![Declaration {
+                        ident: ident(1, "x"),
+                        mutability: Mutability::Mutable,
+                        value: int_literal(2, 1),
+                    }]),
+                },
+                Stmt {
+                    id: 3,
+                    kind: StmtKind::Assignment(Assignment {
+                        ident: ident(1, "x"),
+                        value: int_literal(4, 2),
+                        kind: AssignmentKind::Assign,
+                    }),
+                },
+            ],
+            modules: vec![],
+        };
+
+        assert!(validate_symbols(&ast).is_ok());
+    }
+}
+
+#[cfg(test)]
+mod validate_no_chained_comparisons_test {
+    use super::validate_no_chained_comparisons;
+    use crate::{Assignment, AssignmentKind, BinOp, BinOpKind, Expr, Ident, Literal, LiteralKind, Span, Stmt, StmtKind, AST};
+
+    fn ident(id: usize, name: &str) -> Ident {
+        Ident {
+            id,
+            name: name.to_string(),
+            span: Span(0, 0),
+        }
+    }
+
+    fn int_literal(id: usize, n: i64) -> Expr {
+        Expr::Literal(Literal {
+            id,
+            kind: LiteralKind::Int(n),
+            span: Span(0, 0),
+        })
+    }
+
+    fn ast_for(value: Expr) -> AST {
+        AST {
+            stmts: vec![Stmt {
+                id: 0,
+                kind: StmtKind::Assignment(Assignment {
+                    ident: ident(1, "x"),
+                    value,
+                    kind: AssignmentKind::Assign,
+                }),
+            }],
+            modules: vec![],
+        }
+    }
+
+    #[test]
+    fn rejects_a_directly_chained_comparison() {
+        // (a < b) < c
+        let inner = Expr::BinOp(BinOp {
+            id: 2,
+            lhs: Box::new(int_literal(3, 1)),
+            rhs: Box::new(int_literal(4, 2)),
+            kind: BinOpKind::Lt,
+            span: Span(0, 0),
+        });
+        let outer = Expr::BinOp(BinOp {
+            id: 5,
+            lhs: Box::new(inner),
+            rhs: Box::new(int_literal(6, 3)),
+            kind: BinOpKind::Lt,
+            span: Span(0, 0),
+        });
+
+        assert!(validate_no_chained_comparisons(&ast_for(outer)).is_err());
+    }
+
+    #[test]
+    fn accepts_a_single_comparison() {
+        let expr = Expr::BinOp(BinOp {
+            id: 2,
+            lhs: Box::new(int_literal(3, 1)),
+            rhs: Box::new(int_literal(4, 2)),
+            kind: BinOpKind::Lt,
+            span: Span(0, 0),
+        });
+
+        assert!(validate_no_chained_comparisons(&ast_for(expr)).is_ok());
+    }
+}